@@ -0,0 +1,289 @@
+//! Generates a JSON Schema (draft-07) describing the JSON shape that this crate's `idl2json`
+//! conversion would produce for a given candid type, without needing an actual candid value.
+use candid_parser::types::{Dec, IDLType, IDLTypes, Label, PrimType, TypeField};
+use candid_parser::IDLProg;
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+
+use crate::BytesFormat;
+
+/// Generates a JSON Schema (draft-07) describing the JSON that `idl2json` would produce for a
+/// single `idl_type`. `VarT` references are resolved through `prog`'s top-level declarations,
+/// with recursive types broken by a `$ref` into `$defs`.
+pub fn idl_type_to_json_schema(idl_type: &IDLType, prog: &IDLProg, bytes_as: BytesFormat) -> Value {
+    let mut defs = BTreeMap::new();
+    let mut in_progress = Vec::new();
+    let schema = type_schema(idl_type, prog, bytes_as, &mut defs, &mut in_progress);
+    with_schema_envelope(schema, defs)
+}
+
+/// Generates a JSON Schema (draft-07) describing the JSON that `idl2json` would produce for a
+/// tuple of candid types, such as a method's argument or result list.
+pub fn idl_types_to_json_schema(
+    idl_types: &IDLTypes,
+    prog: &IDLProg,
+    bytes_as: BytesFormat,
+) -> Value {
+    let mut defs = BTreeMap::new();
+    let mut in_progress = Vec::new();
+    let items: Vec<Value> = idl_types
+        .args
+        .iter()
+        .map(|typ| type_schema(typ, prog, bytes_as, &mut defs, &mut in_progress))
+        .collect();
+    let schema = json!({
+        "type": "array",
+        "items": items,
+        "minItems": items.len(),
+        "additionalItems": false,
+    });
+    with_schema_envelope(schema, defs)
+}
+
+fn with_schema_envelope(schema: Value, defs: BTreeMap<String, Value>) -> Value {
+    let mut root = Map::new();
+    root.insert(
+        "$schema".to_string(),
+        json!("http://json-schema.org/draft-07/schema#"),
+    );
+    if !defs.is_empty() {
+        root.insert(
+            "$defs".to_string(),
+            Value::Object(defs.into_iter().collect()),
+        );
+    }
+    match schema {
+        Value::Object(fields) => root.extend(fields),
+        other => {
+            root.insert("allOf".to_string(), json!([other]));
+        }
+    }
+    Value::Object(root)
+}
+
+fn label_name(label: &Label) -> String {
+    match label {
+        Label::Named(name) => name.clone(),
+        Label::Id(id) | Label::Unnamed(id) => id.to_string(),
+    }
+}
+
+fn is_nat8(idl_type: &IDLType) -> bool {
+    matches!(idl_type, IDLType::PrimT(PrimType::Nat8))
+}
+
+fn blob_schema(bytes_as: BytesFormat) -> Value {
+    match bytes_as {
+        BytesFormat::Hex => json!({ "type": "string" }),
+        BytesFormat::Array => json!({ "type": "array", "items": { "type": "integer" } }),
+    }
+}
+
+fn prim_schema(prim: &PrimType) -> Value {
+    match prim {
+        // Large integers are stringified to avoid precision loss in JSON numbers.
+        PrimType::Nat | PrimType::Int | PrimType::Nat64 | PrimType::Int64 => {
+            json!({ "type": "string" })
+        }
+        PrimType::Nat8
+        | PrimType::Nat16
+        | PrimType::Nat32
+        | PrimType::Int8
+        | PrimType::Int16
+        | PrimType::Int32 => json!({ "type": "integer" }),
+        PrimType::Float32 | PrimType::Float64 => json!({ "type": "number" }),
+        PrimType::Bool => json!({ "type": "boolean" }),
+        PrimType::Text => json!({ "type": "string" }),
+        PrimType::Null | PrimType::Reserved => json!({ "type": "null" }),
+        PrimType::Empty => json!({ "not": {} }),
+    }
+}
+
+fn record_schema(
+    fields: &[TypeField],
+    prog: &IDLProg,
+    bytes_as: BytesFormat,
+    defs: &mut BTreeMap<String, Value>,
+    in_progress: &mut Vec<String>,
+) -> Value {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    for field in fields {
+        let name = label_name(&field.label);
+        properties.insert(
+            name.clone(),
+            type_schema(&field.typ, prog, bytes_as, defs, in_progress),
+        );
+        required.push(json!(name));
+    }
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false,
+    })
+}
+
+fn variant_schema(
+    fields: &[TypeField],
+    prog: &IDLProg,
+    bytes_as: BytesFormat,
+    defs: &mut BTreeMap<String, Value>,
+    in_progress: &mut Vec<String>,
+) -> Value {
+    let variants: Vec<Value> = fields
+        .iter()
+        .map(|field| {
+            let name = label_name(&field.label);
+            let inner = type_schema(&field.typ, prog, bytes_as, defs, in_progress);
+            json!({
+                "type": "object",
+                "properties": { name.clone(): inner },
+                "required": [name],
+                "additionalProperties": false,
+            })
+        })
+        .collect();
+    json!({ "oneOf": variants })
+}
+
+/// Resolves a `VarT` reference to a `$ref`, materializing its schema into `$defs` the first
+/// time it is seen. `in_progress` breaks cycles for recursive types.
+fn var_schema(
+    name: &str,
+    prog: &IDLProg,
+    bytes_as: BytesFormat,
+    defs: &mut BTreeMap<String, Value>,
+    in_progress: &mut Vec<String>,
+) -> Value {
+    let ref_schema = json!({ "$ref": format!("#/$defs/{name}") });
+    if defs.contains_key(name) || in_progress.iter().any(|seen| seen == name) {
+        return ref_schema;
+    }
+    let Some(resolved) = prog.decs.iter().find_map(|dec| match dec {
+        Dec::TypD(binding) if binding.id == name => Some(&binding.typ),
+        _ => None,
+    }) else {
+        return json!({ "description": format!("unknown type '{name}'") });
+    };
+    in_progress.push(name.to_string());
+    let schema = type_schema(resolved, prog, bytes_as, defs, in_progress);
+    in_progress.pop();
+    defs.insert(name.to_string(), schema);
+    ref_schema
+}
+
+fn type_schema(
+    idl_type: &IDLType,
+    prog: &IDLProg,
+    bytes_as: BytesFormat,
+    defs: &mut BTreeMap<String, Value>,
+    in_progress: &mut Vec<String>,
+) -> Value {
+    match idl_type {
+        IDLType::PrimT(prim) => prim_schema(prim),
+        IDLType::VarT(name) => var_schema(name, prog, bytes_as, defs, in_progress),
+        IDLType::OptT(inner) => {
+            let inner_schema = type_schema(inner, prog, bytes_as, defs, in_progress);
+            json!({ "anyOf": [inner_schema, { "type": "null" }] })
+        }
+        IDLType::VecT(inner) if is_nat8(inner) => blob_schema(bytes_as),
+        IDLType::VecT(inner) => {
+            let items = type_schema(inner, prog, bytes_as, defs, in_progress);
+            json!({ "type": "array", "items": items })
+        }
+        IDLType::RecordT(fields) => record_schema(fields, prog, bytes_as, defs, in_progress),
+        IDLType::VariantT(fields) => variant_schema(fields, prog, bytes_as, defs, in_progress),
+        IDLType::PrincipalT => json!({ "type": "string" }),
+        IDLType::ServT(_) | IDLType::FuncT(_) | IDLType::ClassT(_, _) => {
+            json!({ "description": "not representable as JSON" })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn prog(did: &str) -> IDLProg {
+        IDLProg::from_str(did).expect("failed to parse .did fixture")
+    }
+
+    #[test]
+    fn primitive_types_map_to_expected_json_types() {
+        let p = prog("service : {}");
+        let schema = idl_type_to_json_schema(&IDLType::PrimT(PrimType::Bool), &p, BytesFormat::Hex);
+        assert_eq!(schema["type"], json!("boolean"));
+
+        // Nat/Int are stringified to avoid precision loss in JSON numbers.
+        let schema = idl_type_to_json_schema(&IDLType::PrimT(PrimType::Nat), &p, BytesFormat::Hex);
+        assert_eq!(schema["type"], json!("string"));
+    }
+
+    #[test]
+    fn nat8_vec_is_a_blob_under_both_bytes_formats() {
+        let p = prog("service : {}");
+        let blob_type = IDLType::VecT(Box::new(IDLType::PrimT(PrimType::Nat8)));
+        let hex_schema = idl_type_to_json_schema(&blob_type, &p, BytesFormat::Hex);
+        assert_eq!(hex_schema["type"], json!("string"));
+        let array_schema = idl_type_to_json_schema(&blob_type, &p, BytesFormat::Array);
+        assert_eq!(array_schema["type"], json!("array"));
+    }
+
+    #[test]
+    fn record_schema_has_required_properties_and_rejects_extras() {
+        let p = prog("service : {}");
+        let record = IDLType::RecordT(vec![
+            TypeField {
+                label: Label::Named("a".to_string()),
+                typ: IDLType::PrimT(PrimType::Bool),
+            },
+            TypeField {
+                label: Label::Named("b".to_string()),
+                typ: IDLType::PrimT(PrimType::Text),
+            },
+        ]);
+        let schema = idl_type_to_json_schema(&record, &p, BytesFormat::Hex);
+        assert_eq!(schema["type"], json!("object"));
+        assert_eq!(schema["additionalProperties"], json!(false));
+        assert!(schema["properties"]["a"].is_object());
+        assert!(schema["required"]
+            .as_array()
+            .expect("required should be an array")
+            .contains(&json!("a")));
+    }
+
+    #[test]
+    fn variant_schema_is_one_of_single_key_objects() {
+        let p = prog("service : {}");
+        let variant = IDLType::VariantT(vec![
+            TypeField {
+                label: Label::Named("ok".to_string()),
+                typ: IDLType::PrimT(PrimType::Text),
+            },
+            TypeField {
+                label: Label::Named("err".to_string()),
+                typ: IDLType::PrimT(PrimType::Text),
+            },
+        ]);
+        let schema = idl_type_to_json_schema(&variant, &p, BytesFormat::Hex);
+        assert_eq!(
+            schema["oneOf"]
+                .as_array()
+                .expect("oneOf should be an array")
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn recursive_var_t_breaks_the_cycle_via_ref() {
+        let p = prog("type list = opt record { head : nat; tail : list }; service : {}");
+        let schema =
+            idl_type_to_json_schema(&IDLType::VarT("list".to_string()), &p, BytesFormat::Hex);
+        assert!(schema["$defs"]["list"].is_object());
+        let tail_ref = &schema["$defs"]["list"]["anyOf"][0]["properties"]["tail"];
+        assert_eq!(tail_ref["$ref"], json!("#/$defs/list"));
+    }
+}
@@ -0,0 +1,7 @@
+//! Converts between candid and JSON (and other JSON-compatible encodings).
+mod reverse_conversion;
+
+pub mod json_schema;
+pub mod polyfill;
+
+pub use reverse_conversion::*;
@@ -0,0 +1,2 @@
+//! Bits of candid introspection that aren't (yet) exposed by `candid_parser` itself.
+pub mod idl_prog;
@@ -0,0 +1,88 @@
+//! Helpers for pulling type information out of a parsed `.did` file.
+use anyhow::{anyhow, Context};
+use candid_parser::types::{Binding, Dec, FuncType, IDLType, IDLTypes};
+use candid_parser::IDLProg;
+
+/// Follows `VarT` references through the program's top-level type declarations
+/// until a non-`VarT` type (or an unresolvable name) is reached. Stops and returns
+/// the current `VarT` if a name is seen twice, so a circular alias (`type A = B;
+/// type B = A;`) can't loop forever.
+fn resolve<'a>(prog: &'a IDLProg, mut typ: &'a IDLType) -> &'a IDLType {
+    let mut seen = Vec::new();
+    while let IDLType::VarT(name) = typ {
+        if seen.iter().any(|s| s == name) {
+            break;
+        }
+        seen.push(name.clone());
+        let next = prog.decs.iter().find_map(|dec| match dec {
+            Dec::TypD(Binding { id, typ }) if id == name => Some(typ),
+            _ => None,
+        });
+        match next {
+            Some(resolved) => typ = resolved,
+            None => break,
+        }
+    }
+    typ
+}
+
+/// Gets the service type of the `.did` file, resolving past a `service : (InitArg) -> { .. }`
+/// class wrapper if present.
+fn get_service_type(prog: &IDLProg) -> anyhow::Result<&IDLType> {
+    let actor = prog
+        .actor
+        .as_ref()
+        .context("The did file has no service definition.")?;
+    match resolve(prog, actor) {
+        IDLType::ClassT(_, service) => Ok(resolve(prog, service)),
+        service @ IDLType::ServT(_) => Ok(service),
+        _ => Err(anyhow!("The did file's service is not a service type.")),
+    }
+}
+
+/// Gets the argument types that should be provided to initialize the canister,
+/// per the `service : (InitArg) -> { .. }` class wrapper, if any.
+pub fn get_init_arg_type(prog: &IDLProg) -> anyhow::Result<IDLTypes> {
+    let actor = prog
+        .actor
+        .as_ref()
+        .context("The did file has no service definition.")?;
+    match resolve(prog, actor) {
+        IDLType::ClassT(args, _) => Ok(IDLTypes { args: args.clone() }),
+        IDLType::ServT(_) => Ok(IDLTypes { args: vec![] }),
+        _ => Err(anyhow!("The did file's service is not a service type.")),
+    }
+}
+
+/// Looks up a method by name in the `.did` file's service and returns its `FuncType`,
+/// resolving both a bare service-level `VarT` and the method's own type if it is a
+/// `VarT` reference rather than an inline `func` type.
+fn get_method_func_type<'a>(prog: &'a IDLProg, method: &str) -> anyhow::Result<&'a FuncType> {
+    let service = get_service_type(prog)?;
+    let bindings = match service {
+        IDLType::ServT(bindings) => bindings,
+        _ => return Err(anyhow!("The did file's service is not a service type.")),
+    };
+    let binding = bindings
+        .iter()
+        .find(|binding| binding.id == method)
+        .with_context(|| anyhow!("The did file has no method named '{method}'."))?;
+    match resolve(prog, &binding.typ) {
+        IDLType::FuncT(func_type) => Ok(func_type),
+        _ => Err(anyhow!("Method '{method}' does not have a function type.")),
+    }
+}
+
+/// Gets the argument tuple type of a named method, for use when encoding a request.
+pub fn get_method_arg_types(prog: &IDLProg, method: &str) -> anyhow::Result<IDLTypes> {
+    Ok(IDLTypes {
+        args: get_method_func_type(prog, method)?.args.clone(),
+    })
+}
+
+/// Gets the result tuple type of a named method, for use when decoding a response.
+pub fn get_method_ret_types(prog: &IDLProg, method: &str) -> anyhow::Result<IDLTypes> {
+    Ok(IDLTypes {
+        args: get_method_func_type(prog, method)?.rets.clone(),
+    })
+}
@@ -3,12 +3,35 @@ use candid_parser::{
     types::{IDLType, IDLTypes},
     IDLArgs, IDLProg,
 };
+use clap::ValueEnum;
 use serde_json::Value as JsonValue;
 use serde_yaml::Value as YamlValue;
 use yaml2candid::Yaml2Candid;
 
-fn json_str_to_value(json_str: &str) -> anyhow::Result<JsonValue> {
-    serde_json::from_str(json_str).with_context(|| anyhow!("Malformed input"))
+/// An encoding that can stand in for JSON when feeding a value into this crate's converters.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum DataFormat {
+    /// JSON
+    #[default]
+    Json,
+    /// YAML
+    Yaml,
+    /// MessagePack
+    Msgpack,
+}
+
+fn data_to_json_value(data: &[u8], format: DataFormat) -> anyhow::Result<JsonValue> {
+    match format {
+        DataFormat::Json => {
+            serde_json::from_slice(data).with_context(|| anyhow!("Malformed input"))
+        }
+        DataFormat::Yaml => {
+            serde_yaml::from_reader(data).with_context(|| anyhow!("Malformed input"))
+        }
+        DataFormat::Msgpack => {
+            rmp_serde::from_slice(data).with_context(|| anyhow!("Malformed input"))
+        }
+    }
 }
 
 fn json_value_to_yaml_value(json_value: &JsonValue) -> anyhow::Result<YamlValue> {
@@ -25,14 +48,15 @@ fn convert_one(
     Ok(idl_value.to_string())
 }
 
-/// Converts one JSON value to one candid value using a named type from a .did file.
+/// Converts one value, in the given format, to one candid value using a named type from a .did file.
 pub fn json2idl_with_type_name(
     prog: IDLProg,
     type_name: &str,
-    json_str: &str,
+    data: &[u8],
+    format: DataFormat,
 ) -> anyhow::Result<String> {
     let converter = Yaml2Candid { prog };
-    let json_value = json_str_to_value(json_str)?;
+    let json_value = data_to_json_value(data, format)?;
     convert_one(
         &converter,
         &IDLType::VarT(type_name.to_string()),
@@ -40,25 +64,27 @@ pub fn json2idl_with_type_name(
     )
 }
 
-/// Converts one JSON value to one candid value using a literal type.
+/// Converts one value, in the given format, to one candid value using a literal type.
 pub fn json2idl_with_type(
     prog: IDLProg,
     idl_type: &IDLType,
-    json_str: &str,
+    data: &[u8],
+    format: DataFormat,
 ) -> anyhow::Result<String> {
     let converter = Yaml2Candid { prog };
-    let json_value = json_str_to_value(json_str)?;
+    let json_value = data_to_json_value(data, format)?;
     convert_one(&converter, idl_type, &json_value)
 }
 
-/// Converts one JSON array to candid args using a tuple/list of candid types.
+/// Converts one array, in the given format, to candid args using a tuple/list of candid types.
 pub fn json_args2idl_with_types(
     prog: IDLProg,
     idl_types: &IDLTypes,
-    json_str: &str,
+    data: &[u8],
+    format: DataFormat,
 ) -> anyhow::Result<String> {
     let converter = Yaml2Candid { prog };
-    let json_value = json_str_to_value(json_str)?;
+    let json_value = data_to_json_value(data, format)?;
     let json_args = json_value
         .as_array()
         .ok_or_else(|| anyhow!("Expected a JSON array"))?;
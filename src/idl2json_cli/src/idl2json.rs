@@ -0,0 +1,11 @@
+use clap::Parser;
+use idl2json_cli as lib;
+use std::io::{self, BufReader};
+
+/// Reads candid from stdin, writes JSON on stdout.
+fn main() {
+    let args = lib::Args::parse();
+    let stdin = io::stdin();
+    lib::run_idl2json(&args, BufReader::new(stdin.lock()), &mut io::stdout())
+        .expect("Failed to convert IDL to JSON");
+}
@@ -18,9 +18,14 @@ use candid_parser::{
 use clap::Parser;
 use idl2json::{
     idl2json, idl2json_with_weak_names, idl_args2json_with_weak_names, json2idl_with_type,
-    json2idl_with_type_name, json_args2idl_with_types, polyfill, BytesFormat, Idl2JsonOptions,
+    json2idl_with_type_name, json_args2idl_with_types, json_schema, polyfill, BytesFormat,
+    DataFormat, Idl2JsonOptions,
+};
+use std::{
+    io::{BufRead, Read, Write},
+    path::PathBuf,
+    str::FromStr,
 };
-use std::{path::PathBuf, str::FromStr};
 
 fn load_did_files(dids: &[PathBuf]) -> anyhow::Result<Vec<IDLProg>> {
     dids.iter()
@@ -33,17 +38,280 @@ fn load_did_files(dids: &[PathBuf]) -> anyhow::Result<Vec<IDLProg>> {
         .collect()
 }
 
-/// Reads IDL from stdin, writes JSON to stdout.
-pub fn main(args: &Args, idl_str: &str) -> anyhow::Result<String> {
-    let idl_args: IDLArgs = parse_idl_args(idl_str).with_context(|| anyhow!("Malformed input"))?;
-    let idl2json_options = Idl2JsonOptions {
+fn build_idl2json_options(args: &Args) -> anyhow::Result<Idl2JsonOptions> {
+    Ok(Idl2JsonOptions {
         prog: load_did_files(&args.did)?,
         bytes_as: args.bytes_as,
         compact: args.compact,
         ..Idl2JsonOptions::default()
+    })
+}
+
+/// Reads IDL from stdin, writes JSON (or the chosen `--output-format`) to stdout.
+pub fn main(args: &Args, idl_str: &str) -> anyhow::Result<Vec<u8>> {
+    let idl2json_options = build_idl2json_options(args)?;
+    convert_record(args, &idl2json_options, idl_str)
+}
+
+/// Reads newline-delimited IDL records from `input`, writing one converted output line per
+/// record to `output`. A record holding several candid values that is converted without a
+/// tuple type (a bare `--typ`, or no type at all) yields one line per value instead. The `.did`
+/// file(s) are loaded once and reused for every line.
+pub fn main_stream(
+    args: &Args,
+    input: impl BufRead,
+    output: &mut impl Write,
+) -> anyhow::Result<()> {
+    let idl2json_options = Idl2JsonOptions {
+        compact: args.effective_compact(),
+        ..build_idl2json_options(args)?
     };
+    for line in input.lines() {
+        let line = line.context("Failed to read a line from stdin")?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match convert_record(args, &idl2json_options, &line) {
+            Ok(bytes) => {
+                output
+                    .write_all(&bytes)
+                    .and_then(|()| output.write_all(b"\n"))
+                    .context("Failed to write to stdout")?;
+            }
+            Err(err) if args.continue_on_error => eprintln!("{err:#}"),
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(())
+}
+
+/// The candid type (or tuple of types) that `--schema`, like the rest of this module, picks out
+/// via `--method`/`--init`/`--typ`, as resolved by `resolve_schema_source` without needing a
+/// value to convert.
+enum SchemaSource {
+    /// A single, possibly compound, candid type.
+    Single(IDLType),
+    /// An ordered tuple of candid types, e.g. a method's argument list.
+    Tuple(IDLTypes),
+}
+
+fn resolve_schema_source(
+    args: &Args,
+    idl2json_options: &Idl2JsonOptions,
+    flag_name: &str,
+) -> anyhow::Result<SchemaSource> {
+    if let Some(method) = &args.method {
+        let prog = idl2json_options
+            .prog
+            .first()
+            .context("Please specify which .did file to use.")?;
+        let idl_types = if args.wants_response() {
+            polyfill::idl_prog::get_method_ret_types(prog, method)
+        } else {
+            polyfill::idl_prog::get_method_arg_types(prog, method)
+        }
+        .with_context(|| anyhow!("Failed to get the type of method '{method}'."))?;
+        Ok(SchemaSource::Tuple(idl_types))
+    } else if args.init {
+        let idl_types = polyfill::idl_prog::get_init_arg_type(
+            idl2json_options
+                .prog
+                .first()
+                .context("Please specify which .did file to use.")?,
+        )
+        .context("Failed to get the service argument from the did file.")?;
+        Ok(SchemaSource::Tuple(idl_types))
+    } else if let Some(typ) = &args.typ {
+        if typ.trim().starts_with('(') {
+            Ok(SchemaSource::Tuple(
+                IDLTypes::from_str(typ).context("Failed to parse type")?,
+            ))
+        } else {
+            Ok(SchemaSource::Single(
+                IDLType::from_str(typ).context("Failed to parse type")?,
+            ))
+        }
+    } else {
+        Err(anyhow!(
+            "Please specify --typ, --init, or --method to describe with {flag_name}."
+        ))
+    }
+}
+
+/// Emits a JSON Schema (draft-07) describing the JSON that `idl2json` would produce for the
+/// resolved type, without reading a candid value from stdin.
+pub fn main_schema(args: &Args) -> anyhow::Result<Vec<u8>> {
+    let idl2json_options = build_idl2json_options(args)?;
+    let prog = idl2json_options
+        .prog
+        .first()
+        .context("Please specify which .did file to use.")?;
+    let bytes_as = idl2json_options.bytes_as.unwrap_or(BytesFormat::Hex);
+    let schema = match resolve_schema_source(args, &idl2json_options, "--schema")? {
+        SchemaSource::Single(idl_type) => {
+            json_schema::idl_type_to_json_schema(&idl_type, prog, bytes_as)
+        }
+        SchemaSource::Tuple(idl_types) => {
+            json_schema::idl_types_to_json_schema(&idl_types, prog, bytes_as)
+        }
+    };
+    (if args.compact {
+        serde_json::to_vec
+    } else {
+        serde_json::to_vec_pretty
+    })(&schema)
+    .context("Failed to serialize schema")
+}
+
+/// Resolves the candid type(s) `--verify` should check `original_args` against. A `--method`/
+/// `--init`/tuple `--typ` already names one type per value, same as plain conversion. A bare
+/// (non-tuple) `--typ`, though, is applied to each top-level value independently by plain
+/// conversion (see `convert_all`) rather than to the whole argument list at once, so it's
+/// repeated here to match arity instead of being wrapped into a single-element tuple.
+fn resolve_verify_types(
+    args: &Args,
+    idl2json_options: &Idl2JsonOptions,
+    arg_count: usize,
+) -> anyhow::Result<IDLTypes> {
+    match resolve_schema_source(args, idl2json_options, "--verify")? {
+        SchemaSource::Tuple(idl_types) => Ok(idl_types),
+        SchemaSource::Single(idl_type) => Ok(IDLTypes {
+            args: std::iter::repeat(idl_type).take(arg_count).collect(),
+        }),
+    }
+}
+
+/// Converts candid on stdin to JSON and back to candid, using the same `--did`/`--typ`/`--init`/
+/// `--method` configuration for both directions, and reports whether the round trip is
+/// lossless. Returns an error (so the caller exits non-zero) on mismatch.
+pub fn main_verify(args: &Args, idl_str: &str) -> anyhow::Result<String> {
+    let idl2json_options = build_idl2json_options(args)?;
+    let original_args: IDLArgs =
+        parse_idl_args(idl_str).with_context(|| anyhow!("Malformed input"))?;
+    let idl_types = resolve_verify_types(args, &idl2json_options, original_args.args.len())?;
+
+    let json_value = idl_args2json_with_weak_names(&original_args, &idl_types, &idl2json_options);
+    let json_bytes = serde_json::to_vec(&json_value).context("Failed to serialize to json")?;
+
+    let prog = idl2json_options
+        .prog
+        .first()
+        .cloned()
+        .context("Please specify which .did file to use.")?;
+    let round_tripped = json_args2idl_with_types(prog, &idl_types, &json_bytes, DataFormat::Json)
+        .context("Failed to convert the intermediate JSON back to candid")?;
+    let round_tripped_args: IDLArgs = parse_idl_args(&round_tripped)
+        .with_context(|| anyhow!("Failed to re-parse the round-tripped candid"))?;
+
+    if original_args.to_string() == round_tripped_args.to_string() {
+        Ok("Round trip OK".to_string())
+    } else {
+        Err(anyhow!(
+            "Round trip mismatch:\n- original:      {original_args}\n- round-tripped: {round_tripped_args}"
+        ))
+    }
+}
+
+/// Entry point for the `idl2json` binary: reads stdin (in streaming or whole-document mode,
+/// depending on `args.stream`), or, with `--schema`/`--verify`, bypasses the usual conversion.
+pub fn run_idl2json(
+    args: &Args,
+    mut input: impl BufRead,
+    output: &mut impl Write,
+) -> anyhow::Result<()> {
+    if args.schema {
+        let bytes = main_schema(args)?;
+        return output
+            .write_all(&bytes)
+            .and_then(|()| output.write_all(b"\n"))
+            .context("Failed to write to stdout");
+    }
+    if args.verify {
+        let mut buffer = String::new();
+        input
+            .read_to_string(&mut buffer)
+            .context("Failed to read from stdin")?;
+        let report = main_verify(args, &buffer)?;
+        return writeln!(output, "{report}").context("Failed to write to stdout");
+    }
+    if args.stream {
+        match args.output_format.unwrap_or_default() {
+            DataFormat::Msgpack => {
+                return Err(anyhow!(
+                    "--stream frames records by newline, which MessagePack output isn't safe \
+                     to share a line with; pick --output-format json instead."
+                ))
+            }
+            DataFormat::Yaml => {
+                return Err(anyhow!(
+                    "--stream frames records by newline, but YAML's block style embeds a \
+                     literal newline for non-scalar values; pick --output-format json instead."
+                ))
+            }
+            DataFormat::Json => {}
+        }
+        main_stream(args, input, output)
+    } else {
+        let mut buffer = String::new();
+        input
+            .read_to_string(&mut buffer)
+            .context("Failed to read from stdin")?;
+        let bytes = main(args, &buffer)?;
+        output
+            .write_all(&bytes)
+            .and_then(|()| output.write_all(b"\n"))
+            .context("Failed to write to stdout")
+    }
+}
+
+/// Serializes a converted JSON value using the output format and compactness requested on the
+/// command line.
+fn serialize_output(
+    json_value: &serde_json::Value,
+    format: DataFormat,
+    compact: bool,
+) -> anyhow::Result<Vec<u8>> {
+    match format {
+        DataFormat::Json => (if compact {
+            serde_json::to_vec
+        } else {
+            serde_json::to_vec_pretty
+        })(json_value)
+        .context("Failed to serialize to json"),
+        DataFormat::Yaml => serde_yaml::to_string(json_value)
+            .map(String::into_bytes)
+            .context("Failed to serialize to yaml"),
+        DataFormat::Msgpack => {
+            rmp_serde::to_vec(json_value).context("Failed to serialize to msgpack")
+        }
+    }
+}
+
+fn convert_record(
+    args: &Args,
+    idl2json_options: &Idl2JsonOptions,
+    idl_str: &str,
+) -> anyhow::Result<Vec<u8>> {
+    let idl_args: IDLArgs = parse_idl_args(idl_str).with_context(|| anyhow!("Malformed input"))?;
+    let format = args.output_format.unwrap_or_default();
     // Decide what to do
-    if args.init {
+    if let Some(method) = &args.method {
+        let prog = idl2json_options
+            .prog
+            .first()
+            .context("Please specify which .did file to use.")?;
+        let idl_types = if args.wants_response() {
+            polyfill::idl_prog::get_method_ret_types(prog, method)
+        } else {
+            polyfill::idl_prog::get_method_arg_types(prog, method)
+        }
+        .with_context(|| anyhow!("Failed to get the type of method '{method}'."))?;
+        serialize_output(
+            &idl_args2json_with_weak_names(&idl_args, &idl_types, idl2json_options),
+            format,
+            idl2json_options.compact,
+        )
+    } else if args.init {
         // Use the type of the .did file init arg.
         // - If multiple did files are provided, the first is used.
         // - Clap should reject commands without a --did file.
@@ -54,48 +322,119 @@ pub fn main(args: &Args, idl_str: &str) -> anyhow::Result<String> {
                 .context("Please specify which .did file to use.")?,
         )
         .context("Failed to get the service argument from the did file.")?;
-        serde_json::to_string(&idl_args2json_with_weak_names(
-            &idl_args,
-            &idl_types,
-            &idl2json_options,
-        ))
-        .context("Failed to serialize to json")
+        serialize_output(
+            &idl_args2json_with_weak_names(&idl_args, &idl_types, idl2json_options),
+            format,
+            idl2json_options.compact,
+        )
     } else if let Some(idl_type) = &args.typ {
         if idl_type.trim().starts_with('(') {
             let idl_types = IDLTypes::from_str(idl_type).context("Failed to parse type")?;
-            serde_json::to_string(&idl_args2json_with_weak_names(
-                &idl_args,
-                &idl_types,
-                &idl2json_options,
-            ))
-            .context("Failed to serialize to json")
+            serialize_output(
+                &idl_args2json_with_weak_names(&idl_args, &idl_types, idl2json_options),
+                format,
+                idl2json_options.compact,
+            )
         } else {
             let idl_type = IDLType::from_str(idl_type).context("Failed to parse type")?;
-            convert_all(&idl_args, &Some(idl_type), &idl2json_options)
+            convert_all(&idl_args, &Some(idl_type), idl2json_options, format)
         }
     } else {
-        convert_all(&idl_args, &None, &idl2json_options)
+        convert_all(&idl_args, &None, idl2json_options, format)
     }
 }
 
-/// Reads JSON from stdin, writes candid to stdout.
-pub fn main_json2idl(args: &Json2IdlArgs, json_str: &str) -> anyhow::Result<String> {
-    let did_prog = load_did_files(&args.did)?
-        .into_iter()
-        .next()
-        .unwrap_or(IDLProg {
-            decs: vec![],
-            actor: None,
-        });
+fn build_did_prog(dids: &[PathBuf]) -> anyhow::Result<IDLProg> {
+    Ok(load_did_files(dids)?.into_iter().next().unwrap_or(IDLProg {
+        decs: vec![],
+        actor: None,
+    }))
+}
+
+/// Reads JSON (or the chosen `--input-format`) from stdin, writes candid to stdout.
+pub fn main_json2idl(args: &Json2IdlArgs, data: &[u8]) -> anyhow::Result<String> {
+    let did_prog = build_did_prog(&args.did)?;
+    convert_record_json2idl(args, did_prog, data)
+}
 
-    if args.init {
+/// Reads newline-delimited records from `input`, writing one converted candid line per
+/// record to `output`. The `.did` file(s) are loaded once and reused for every line.
+pub fn main_json2idl_stream(
+    args: &Json2IdlArgs,
+    input: impl BufRead,
+    output: &mut impl Write,
+) -> anyhow::Result<()> {
+    let did_prog = build_did_prog(&args.did)?;
+    for line in input.lines() {
+        let line = line.context("Failed to read a line from stdin")?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match convert_record_json2idl(args, did_prog.clone(), line.as_bytes()) {
+            Ok(idl_str) => writeln!(output, "{idl_str}").context("Failed to write to stdout")?,
+            Err(err) if args.continue_on_error => eprintln!("{err:#}"),
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(())
+}
+
+/// Entry point for the `json2idl` binary: reads stdin (in streaming or whole-document mode,
+/// depending on `args.stream`) and writes the converted candid to `output`.
+pub fn run_json2idl(
+    args: &Json2IdlArgs,
+    mut input: impl BufRead,
+    output: &mut impl Write,
+) -> anyhow::Result<()> {
+    if args.stream {
+        match args.input_format.unwrap_or_default() {
+            DataFormat::Msgpack => {
+                return Err(anyhow!(
+                    "--stream frames records by newline, which MessagePack input isn't safe \
+                     to share a line with; pick --input-format json instead."
+                ))
+            }
+            DataFormat::Yaml => {
+                return Err(anyhow!(
+                    "--stream frames records by newline, but YAML's block style embeds a \
+                     literal newline for non-scalar values; pick --input-format json instead."
+                ))
+            }
+            DataFormat::Json => {}
+        }
+        main_json2idl_stream(args, input, output)
+    } else {
+        let mut buffer = Vec::new();
+        input
+            .read_to_end(&mut buffer)
+            .context("Failed to read from stdin")?;
+        let idl_str = main_json2idl(args, &buffer)?;
+        writeln!(output, "{idl_str}").context("Failed to write to stdout")
+    }
+}
+
+fn convert_record_json2idl(
+    args: &Json2IdlArgs,
+    did_prog: IDLProg,
+    data: &[u8],
+) -> anyhow::Result<String> {
+    let format = args.input_format.unwrap_or_default();
+    if let Some(method) = &args.method {
+        let idl_types = if args.wants_response() {
+            polyfill::idl_prog::get_method_ret_types(&did_prog, method)
+        } else {
+            polyfill::idl_prog::get_method_arg_types(&did_prog, method)
+        }
+        .with_context(|| anyhow!("Failed to get the type of method '{method}'."))?;
+        json_args2idl_with_types(did_prog, &idl_types, data, format)
+    } else if args.init {
         let init_arg_types = polyfill::idl_prog::get_init_arg_type(&did_prog)
             .context("Failed to get the service argument from the did file.")?;
-        json_args2idl_with_types(did_prog, &init_arg_types, json_str)
+        json_args2idl_with_types(did_prog, &init_arg_types, data, format)
     } else if let Some(typ) = &args.typ {
         if typ.trim().starts_with('(') {
             let idl_types = IDLTypes::from_str(typ).context("Failed to parse type")?;
-            json_args2idl_with_types(did_prog, &idl_types, json_str)
+            json_args2idl_with_types(did_prog, &idl_types, data, format)
         } else {
             let trimmed_typ = typ.trim();
             if !trimmed_typ.contains(char::is_whitespace)
@@ -104,10 +443,10 @@ pub fn main_json2idl(args: &Json2IdlArgs, json_str: &str) -> anyhow::Result<Stri
                 && !trimmed_typ.contains(':')
                 && !trimmed_typ.contains(';')
             {
-                json2idl_with_type_name(did_prog, trimmed_typ, json_str)
+                json2idl_with_type_name(did_prog, trimmed_typ, data, format)
             } else {
                 let idl_type = IDLType::from_str(trimmed_typ).context("Failed to parse type")?;
-                json2idl_with_type(did_prog, &idl_type, json_str)
+                json2idl_with_type(did_prog, &idl_type, data, format)
             }
         }
     } else {
@@ -122,18 +461,14 @@ fn convert_one(
     idl_value: &IDLValue,
     idl_type: &Option<IDLType>,
     idl2json_options: &Idl2JsonOptions,
-) -> anyhow::Result<String> {
+    format: DataFormat,
+) -> anyhow::Result<Vec<u8>> {
     let json_value = if let Some(idl_type) = idl_type {
         idl2json_with_weak_names(idl_value, idl_type, idl2json_options)
     } else {
         idl2json(idl_value, idl2json_options)
     };
-    (if idl2json_options.compact {
-        serde_json::to_string
-    } else {
-        serde_json::to_string_pretty
-    })(&json_value)
-    .with_context(|| anyhow!("Cannot print to stderr"))
+    serialize_output(&json_value, format, idl2json_options.compact)
 }
 
 /// Candid typically comes as a tuple of values.  This converts all such tuples
@@ -141,13 +476,21 @@ fn convert_all(
     idl_args: &IDLArgs,
     idl_type: &Option<IDLType>,
     idl2json_options: &Idl2JsonOptions,
-) -> anyhow::Result<String> {
-    let json_structures: anyhow::Result<Vec<String>> = idl_args
+    format: DataFormat,
+) -> anyhow::Result<Vec<u8>> {
+    if idl_args.args.len() > 1 && format == DataFormat::Msgpack {
+        return Err(anyhow!(
+            "Converting more than one value at once isn't supported for --output-format \
+             msgpack, since a newline can legitimately appear inside a MessagePack value; \
+             use --typ with a tuple type to convert them as one value instead."
+        ));
+    }
+    let json_structures: anyhow::Result<Vec<Vec<u8>>> = idl_args
         .args
         .iter()
-        .map(|idl_value| convert_one(idl_value, idl_type, idl2json_options))
+        .map(|idl_value| convert_one(idl_value, idl_type, idl2json_options, format))
         .collect();
-    Ok(json_structures?.join("\n"))
+    Ok(json_structures?.join(&b'\n'))
 }
 
 /// Converts Candid on stdin to JSON on stdout.
@@ -163,12 +506,57 @@ pub struct Args {
     /// Use the service init argument type from the did file
     #[clap(short, long, requires("did"))]
     init: bool,
+    /// The name of a method in the provided .did file; its request or response type is used
+    #[clap(short, long, requires("did"), conflicts_with_all(["typ", "init"]))]
+    method: Option<String>,
+    /// With --method, use the method's request (argument) type [default]
+    #[clap(long, requires("method"), conflicts_with("response"))]
+    request: bool,
+    /// With --method, use the method's response (result) type instead of its request type
+    #[clap(long, requires("method"), conflicts_with("request"))]
+    response: bool,
     /// How to display bytes
     #[clap(short, long, value_enum)]
     bytes_as: Option<BytesFormat>,
     /// Print compact output
     #[clap(short, long)]
     compact: bool,
+    /// The encoding to emit instead of JSON
+    #[clap(long = "output-format", value_enum)]
+    output_format: Option<DataFormat>,
+    /// Emit a JSON Schema for the resolved type instead of converting a value from stdin
+    #[clap(long, conflicts_with_all(["stream", "verify"]))]
+    schema: bool,
+    /// Round-trip the candid on stdin through JSON and back, reporting (and failing on) any mismatch
+    #[clap(long, conflicts_with_all(["stream", "schema"]))]
+    verify: bool,
+    /// Treat stdin as newline-delimited candid records, converting and emitting one JSON line per input line (one per value for a multi-value record without a tuple type)
+    #[clap(long)]
+    stream: bool,
+    /// With --stream, report a malformed line to stderr and continue instead of aborting
+    #[clap(long, requires("stream"))]
+    continue_on_error: bool,
+}
+
+impl Args {
+    /// Whether `--method`'s response (result) type should be used instead of its request
+    /// (argument) type. `--request` is the default and only exists so it can be spelled out
+    /// explicitly; clap already rejects passing both flags together.
+    fn wants_response(&self) -> bool {
+        debug_assert!(
+            !(self.request && self.response),
+            "clap should reject --request together with --response"
+        );
+        self.response
+    }
+
+    /// Whether output should be compact. `--stream` writes each converted value on a single
+    /// line, so pretty-printing (the default) would desync a downstream consumer the moment a
+    /// non-scalar value is converted; compact output is forced in that mode regardless of
+    /// `--compact`.
+    fn effective_compact(&self) -> bool {
+        self.compact || self.stream
+    }
 }
 
 /// Converts JSON on stdin to Candid on stdout.
@@ -184,4 +572,35 @@ pub struct Json2IdlArgs {
     /// Use the service init argument type from the did file
     #[clap(short, long, requires("did"))]
     init: bool,
+    /// The name of a method in the provided .did file; its request or response type is used
+    #[clap(short, long, requires("did"), conflicts_with_all(["typ", "init"]))]
+    method: Option<String>,
+    /// With --method, use the method's request (argument) type [default]
+    #[clap(long, requires("method"), conflicts_with("response"))]
+    request: bool,
+    /// With --method, use the method's response (result) type instead of its request type
+    #[clap(long, requires("method"), conflicts_with("request"))]
+    response: bool,
+    /// The encoding stdin is read as, instead of JSON
+    #[clap(long = "input-format", value_enum)]
+    input_format: Option<DataFormat>,
+    /// Treat stdin as newline-delimited JSON records, converting and emitting one candid line per input line
+    #[clap(long)]
+    stream: bool,
+    /// With --stream, report a malformed line to stderr and continue instead of aborting
+    #[clap(long, requires("stream"))]
+    continue_on_error: bool,
+}
+
+impl Json2IdlArgs {
+    /// Whether `--method`'s response (result) type should be used instead of its request
+    /// (argument) type. `--request` is the default and only exists so it can be spelled out
+    /// explicitly; clap already rejects passing both flags together.
+    fn wants_response(&self) -> bool {
+        debug_assert!(
+            !(self.request && self.response),
+            "clap should reject --request together with --response"
+        );
+        self.response
+    }
 }
@@ -0,0 +1,138 @@
+use super::*;
+
+#[test]
+fn method_conflicts_with_typ_and_init() {
+    assert!(Args::try_parse_from(["idl2json", "-d", "a.did", "-m", "foo", "-t", "bar"]).is_err());
+    assert!(Args::try_parse_from(["idl2json", "-d", "a.did", "-m", "foo", "-i"]).is_err());
+    assert!(
+        Json2IdlArgs::try_parse_from(["json2idl", "-d", "a.did", "-m", "foo", "-t", "bar"])
+            .is_err()
+    );
+}
+
+#[test]
+fn request_and_response_are_mutually_exclusive() {
+    assert!(Args::try_parse_from([
+        "idl2json",
+        "-d",
+        "a.did",
+        "-m",
+        "foo",
+        "--request",
+        "--response"
+    ])
+    .is_err());
+}
+
+#[test]
+fn response_requires_method() {
+    assert!(Args::try_parse_from(["idl2json", "--response"]).is_err());
+    assert!(Json2IdlArgs::try_parse_from(["json2idl", "--response"]).is_err());
+}
+
+#[test]
+fn schema_conflicts_with_stream_and_verify() {
+    assert!(Args::try_parse_from(["idl2json", "--schema", "--stream"]).is_err());
+    assert!(Args::try_parse_from(["idl2json", "--schema", "--verify"]).is_err());
+}
+
+#[test]
+fn continue_on_error_requires_stream() {
+    assert!(Args::try_parse_from(["idl2json", "--continue-on-error"]).is_err());
+    assert!(Json2IdlArgs::try_parse_from(["json2idl", "--continue-on-error"]).is_err());
+}
+
+#[test]
+fn input_and_output_format_default_to_json() {
+    let args = Args::try_parse_from(["idl2json"]).expect("should parse with no flags");
+    assert_eq!(args.output_format.unwrap_or_default(), DataFormat::Json);
+    let args = Json2IdlArgs::try_parse_from(["json2idl"]).expect("should parse with no flags");
+    assert_eq!(args.input_format.unwrap_or_default(), DataFormat::Json);
+}
+
+#[test]
+fn stream_rejects_msgpack_output() {
+    let args = Args::try_parse_from(["idl2json", "--stream", "--output-format", "msgpack"])
+        .expect("should parse");
+    let mut output = Vec::new();
+    assert!(run_idl2json(&args, "".as_bytes(), &mut output).is_err());
+}
+
+#[test]
+fn stream_rejects_msgpack_input() {
+    let args = Json2IdlArgs::try_parse_from(["json2idl", "--stream", "--input-format", "msgpack"])
+        .expect("should parse");
+    let mut output = Vec::new();
+    assert!(run_json2idl(&args, "".as_bytes(), &mut output).is_err());
+}
+
+#[test]
+fn stream_rejects_yaml_output() {
+    let args = Args::try_parse_from(["idl2json", "--stream", "--output-format", "yaml"])
+        .expect("should parse");
+    let mut output = Vec::new();
+    assert!(run_idl2json(&args, "".as_bytes(), &mut output).is_err());
+}
+
+#[test]
+fn stream_rejects_yaml_input() {
+    let args = Json2IdlArgs::try_parse_from(["json2idl", "--stream", "--input-format", "yaml"])
+        .expect("should parse");
+    let mut output = Vec::new();
+    assert!(run_json2idl(&args, "".as_bytes(), &mut output).is_err());
+}
+
+#[test]
+fn stream_forces_compact_output() {
+    let args = Args::try_parse_from(["idl2json", "--stream"]).expect("should parse");
+    assert!(args.effective_compact());
+    let args = Args::try_parse_from(["idl2json"]).expect("should parse");
+    assert!(!args.effective_compact());
+    let args = Args::try_parse_from(["idl2json", "--compact"]).expect("should parse");
+    assert!(args.effective_compact());
+}
+
+fn write_temp_did(name: &str, contents: &str) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "idl2json_cli_test_{name}_{}.did",
+        std::process::id()
+    ));
+    std::fs::write(&path, contents).expect("failed to write temp .did fixture");
+    path
+}
+
+#[test]
+fn verify_reports_ok_for_a_lossless_round_trip() {
+    let did_path = write_temp_did("verify_ok", "service : {}");
+    let args = Args::try_parse_from([
+        "idl2json",
+        "--did",
+        did_path.to_str().expect("path should be valid utf8"),
+        "--typ",
+        "text",
+        "--verify",
+    ])
+    .expect("should parse");
+    let report = main_verify(&args, "(\"hello\")");
+    let _ = std::fs::remove_file(&did_path);
+    assert_eq!(report.expect("round trip should succeed"), "Round trip OK");
+}
+
+#[test]
+fn verify_applies_a_bare_typ_per_value_not_to_the_whole_tuple() {
+    // A bare (non-tuple) --typ is applied to each top-level value independently, the same
+    // as plain conversion -- it must not be treated as a single type for the whole tuple.
+    let did_path = write_temp_did("verify_arity", "service : {}");
+    let args = Args::try_parse_from([
+        "idl2json",
+        "--did",
+        did_path.to_str().expect("path should be valid utf8"),
+        "--typ",
+        "nat",
+        "--verify",
+    ])
+    .expect("should parse");
+    let report = main_verify(&args, "(1, 2, 3)");
+    let _ = std::fs::remove_file(&did_path);
+    assert_eq!(report.expect("round trip should succeed"), "Round trip OK");
+}